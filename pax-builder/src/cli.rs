@@ -14,9 +14,13 @@ use axum::{
 use std::fs;
 use tower_http::services::ServeDir;
 
+mod pr_bumper;
 mod version_checker;
 mod worker;
 
+use pr_bumper::PrBumper;
+use version_checker::VersionStatus;
+
 #[derive(Parser)]
 #[command(name = "pax-build-infra")]
 #[command(about = "PAX Build Infrastructure - Automated build system with web GUI")]
@@ -52,6 +56,16 @@ enum Commands {
         #[arg(long, default_value = "4")]
         workers: usize,
     },
+    /// Open (or update) pull requests bumping every recipe with an available
+    /// upstream update. Requires `GITHUB_TOKEN` unless `--dry-run` is set.
+    BumpOutdated {
+        #[arg(long, default_value = "./recipes")]
+        recipes_dir: PathBuf,
+        #[arg(long, default_value = "oreonproject/paxbuild-recipes")]
+        repo: String,
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 #[tokio::main]
@@ -89,6 +103,46 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 server_url, workers
             );
         }
+        Commands::BumpOutdated {
+            recipes_dir,
+            repo,
+            dry_run,
+        } => {
+            bump_outdated_recipes(&recipes_dir, &repo, dry_run).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Scan `recipes_dir` for packages with `VersionStatus::UpdateAvailable` and
+/// open (or update) a pull request bumping each one, via `PrBumper`.
+async fn bump_outdated_recipes(
+    recipes_dir: &Path,
+    repo: &str,
+    dry_run: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let token = std::env::var("GITHUB_TOKEN").ok();
+    if token.is_none() && !dry_run {
+        println!("GITHUB_TOKEN not set — falling back to dry-run logging only");
+    }
+
+    let bumper = PrBumper::new(repo.to_string(), token, dry_run);
+    let versions = version_checker::VersionChecker::check_all_packages(recipes_dir).await?;
+
+    for version in &versions {
+        if version.status != VersionStatus::UpdateAvailable {
+            continue;
+        }
+        let recipe_dir = recipes_dir.join(&version.name);
+        let repo_relative_path = Path::new(&version.name).join("pax.yaml");
+        match bumper
+            .bump_recipe(&recipe_dir, &repo_relative_path, version)
+            .await
+        {
+            Ok(branch) => println!("Bumped {} on branch {}", version.name, branch),
+            Err(err) => println!("Failed to bump {}: {}", version.name, err),
+        }
     }
 
     Ok(())
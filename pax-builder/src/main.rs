@@ -1,99 +1,454 @@
 use std::{
-    env,
+    collections::HashSet,
+    env, fs, io,
     path::{Path, PathBuf},
     process::exit,
 };
 
-use pax_builder::{BuiltPackage, PaxPackageBuilder, TargetArch};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::{generate, Shell};
+use pax_builder::{
+    BuildPhase, BuiltPackage, InstallMessage, Installer, PaxPackageBuilder, TargetArch,
+    VersionComponent,
+};
+use serde_json::json;
+
+#[derive(Parser)]
+#[command(name = "pax-builder", about = "PAX Package Builder", version)]
+struct Cli {
+    /// Change to this directory before resolving the spec path and running the command
+    #[arg(short = 'C', long = "directory", global = true)]
+    directory: Option<PathBuf>,
+
+    /// Load builder settings from a paxbuild.toml, layered over
+    /// /etc/paxbuild.toml and ~/.config/paxbuild.toml
+    #[arg(short = 'c', long = "config", global = true)]
+    config: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
+#[derive(Subcommand)]
+enum Command {
+    /// Build a package from a specification file
+    Build {
+        /// Path to the package specification file (pax.yaml)
+        spec: PathBuf,
+        /// Enable verbose output
+        #[arg(short, long)]
+        verbose: bool,
+        /// Cross-compile for target architecture (x86_64v1, x86_64v3, aarch64, armv7l, riscv64, etc.)
+        #[arg(long, value_parser = parse_target_arch, conflicts_with = "all_targets")]
+        target: Option<TargetArch>,
+        /// Build every architecture listed in build.target_architectures, in parallel
+        #[arg(long = "all-targets")]
+        all_targets: bool,
+        /// Specify output directory for packages (default: current directory)
+        #[arg(long = "output-dir")]
+        output_dir: Option<PathBuf>,
+        /// Disable bubblewrap build isolation
+        #[arg(long = "no-bubblewrap")]
+        no_bubblewrap: bool,
+        /// Download sources but skip checksum/GPG verification
+        #[arg(long = "skip-integrity")]
+        skip_integrity: bool,
+        /// Skip resolving and auto-building declared build dependencies
+        #[arg(long = "no-deps")]
+        no_deps: bool,
+        /// Wipe the build directory before building
+        #[arg(long = "clean")]
+        clean: bool,
+        /// Skip the build and reuse the existing output artifact if one already matches
+        #[arg(long = "needed")]
+        needed: bool,
+        /// GPG key id to detach-sign every built artifact with (implies --manifest)
+        #[arg(long = "sign")]
+        sign: Option<String>,
+        /// Write a release-manifest.json describing every built artifact
+        #[arg(long = "manifest")]
+        manifest: bool,
+        /// Output format for the build result
+        #[arg(long = "message-format", value_enum, default_value_t = MessageFormat::Human)]
+        message_format: MessageFormat,
+    },
+    /// Validate a package specification file
+    Validate {
+        /// Path to the package specification file (pax.yaml)
+        spec: PathBuf,
+        /// Output format for validation errors
+        #[arg(long = "message-format", value_enum, default_value_t = MessageFormat::Human)]
+        message_format: MessageFormat,
+    },
+    /// Create a new package template
+    Init {
+        /// Name of the package to scaffold
+        name: String,
+        /// Directory to write the template into (default: current directory)
+        output_dir: Option<PathBuf>,
+    },
+    /// Bump the spec's version (major, minor, or patch)
+    Bump {
+        /// Which semver component to increment
+        #[arg(value_enum)]
+        component: BumpComponent,
+        /// Path to the package specification file (pax.yaml)
+        spec: PathBuf,
+        /// Pre-release label to attach to the bumped version (e.g. rc.1)
+        #[arg(long)]
+        pre: Option<String>,
+    },
+    /// Download and verify declared sources without building
+    Fetch {
+        /// Path to the package specification file (pax.yaml)
+        spec: PathBuf,
+    },
+    /// Run a sub-range of the fetch/prepare/configure/build/install/package
+    /// pipeline, resuming from whatever phase is still cached
+    Phase {
+        /// Path to the package specification file (pax.yaml)
+        spec: PathBuf,
+        /// First phase to (re)run
+        #[arg(long, value_enum, default_value_t = PhaseArg::Fetch)]
+        from: PhaseArg,
+        /// Last phase to run
+        #[arg(long, value_enum, default_value_t = PhaseArg::Package)]
+        to: PhaseArg,
+    },
+    /// Install a built .pax archive into an install root
+    Install {
+        /// Path to the .pax archive
+        archive: PathBuf,
+        /// Install root (default: /)
+        #[arg(long)]
+        root: Option<PathBuf>,
+    },
+    /// Clean the build directory
+    Clean,
+    /// Generate a shell completion script for pax-builder
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
 
-    if args.len() < 2 {
-        print_usage();
-        exit(1);
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum MessageFormat {
+    Human,
+    Json,
+}
+
+impl std::fmt::Display for MessageFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MessageFormat::Human => write!(f, "human"),
+            MessageFormat::Json => write!(f, "json"),
+        }
     }
+}
 
-    let command = &args[1];
+#[derive(Clone, Copy, ValueEnum)]
+enum BumpComponent {
+    Major,
+    Minor,
+    Patch,
+}
 
-    match command.as_str() {
-        "build" => {
-            if args.len() < 3 {
-                eprintln!("Error: Package specification file required");
-                print_usage();
-                exit(1);
-            }
+#[derive(Clone, Copy, ValueEnum)]
+enum PhaseArg {
+    Fetch,
+    Prepare,
+    Configure,
+    Build,
+    Install,
+    Package,
+}
+
+impl From<PhaseArg> for BuildPhase {
+    fn from(phase: PhaseArg) -> Self {
+        match phase {
+            PhaseArg::Fetch => BuildPhase::Fetch,
+            PhaseArg::Prepare => BuildPhase::Prepare,
+            PhaseArg::Configure => BuildPhase::Configure,
+            PhaseArg::Build => BuildPhase::Build,
+            PhaseArg::Install => BuildPhase::Install,
+            PhaseArg::Package => BuildPhase::Package,
+        }
+    }
+}
+
+impl From<BumpComponent> for VersionComponent {
+    fn from(component: BumpComponent) -> Self {
+        match component {
+            BumpComponent::Major => VersionComponent::Major,
+            BumpComponent::Minor => VersionComponent::Minor,
+            BumpComponent::Patch => VersionComponent::Patch,
+        }
+    }
+}
+
+/// Construct a `PaxPackageBuilder` the way every subcommand wants it built:
+/// layered from `config` (and `/etc/paxbuild.toml`/`~/.config/paxbuild.toml`)
+/// when `-c`/`--config` was given, or the plain built-in defaults otherwise.
+fn make_builder(config: &Option<PathBuf>) -> Result<PaxPackageBuilder, String> {
+    match config {
+        Some(path) => PaxPackageBuilder::from_config(path),
+        None => PaxPackageBuilder::new(),
+    }
+}
+
+fn parse_target_arch(value: &str) -> Result<TargetArch, String> {
+    TargetArch::from_str(value).ok_or_else(|| format!("Unknown target architecture '{}'", value))
+}
+
+const BUILTIN_COMMANDS: &[&str] = &[
+    "build",
+    "validate",
+    "init",
+    "bump",
+    "fetch",
+    "phase",
+    "install",
+    "clean",
+    "completions",
+    "help",
+    "--help",
+    "-h",
+    "--version",
+    "-V",
+];
+
+/// Expand a user-defined alias from `.pax-builder.toml`'s `[alias]` table
+/// into `args`, splicing the expansion in place of the alias name. Falls
+/// through unchanged for builtin commands or when no config/alias is found.
+/// Guards against an alias that (directly or transitively) expands back
+/// into itself.
+fn resolve_aliases(args: &[String]) -> Vec<String> {
+    let mut args = args.to_vec();
+    let mut seen = HashSet::new();
+
+    loop {
+        let Some(index) = find_subcommand_index(&args) else {
+            return args;
+        };
+        let name = args[index].clone();
+        if BUILTIN_COMMANDS.contains(&name.as_str()) {
+            return args;
+        }
+        if !seen.insert(name.clone()) {
+            eprintln!(
+                "Error: alias '{}' expands into itself (recursive alias loop)",
+                name
+            );
+            exit(1);
+        }
+
+        let Some(expansion) = lookup_alias(&name) else {
+            return args;
+        };
+        args.splice(index..index + 1, expansion);
+    }
+}
 
-            let spec_path = Path::new(&args[2]);
-            let verbose =
-                args.contains(&"--verbose".to_string()) || args.contains(&"-v".to_string());
+/// Find the index of the subcommand token in `args`, skipping `argv[0]`
+/// and the global `-C`/`--directory <dir>` flag.
+fn find_subcommand_index(args: &[String]) -> Option<usize> {
+    let mut i = 1;
+    while i < args.len() {
+        let arg = &args[i];
+        if arg == "-C" || arg == "--directory" {
+            i += 2;
+            continue;
+        }
+        if arg.starts_with("--directory=") {
+            i += 1;
+            continue;
+        }
+        return Some(i);
+    }
+    None
+}
 
-            // Parse --target flag
-            let target_arch = parse_target_flag(&args);
+fn lookup_alias(name: &str) -> Option<Vec<String>> {
+    let table = load_alias_table()?;
+    match table.get(name)? {
+        toml::Value::String(expansion) => {
+            Some(expansion.split_whitespace().map(str::to_string).collect())
+        }
+        toml::Value::Array(items) => Some(
+            items
+                .iter()
+                .filter_map(|item| item.as_str().map(str::to_string))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
 
-            // Parse --output-dir flag
-            let output_dir = parse_output_dir_flag(&args);
+/// Search upward from the current directory for `.pax-builder.toml`, the
+/// way cargo discovers `.cargo/config.toml`, and return its `[alias]` table.
+fn load_alias_table() -> Option<toml::value::Table> {
+    let mut dir = env::current_dir().ok()?;
+    loop {
+        let candidate = dir.join(".pax-builder.toml");
+        if candidate.is_file() {
+            let contents = fs::read_to_string(&candidate).ok()?;
+            let parsed: toml::Value = toml::from_str(&contents).ok()?;
+            return parsed.get("alias")?.as_table().cloned();
+        }
+        if !dir.pop() {
+            return None;
+        }
+    }
+}
 
-            // Parse --no-bubblewrap flag
-            let use_bubblewrap = !args.contains(&"--no-bubblewrap".to_string());
+fn main() {
+    let raw_args: Vec<String> = env::args().collect();
+    let args = resolve_aliases(&raw_args);
+    let cli = Cli::parse_from(args);
+
+    if let Some(directory) = &cli.directory {
+        if let Err(e) = std::env::set_current_dir(directory) {
+            eprintln!(
+                "Error: Failed to change directory to {}: {}",
+                directory.display(),
+                e
+            );
+            exit(1);
+        }
+    }
 
-            match build_package(spec_path, verbose, target_arch, output_dir, use_bubblewrap) {
-                Ok(built_packages) => {
-                    println!("Package built successfully!");
-                    for built_package in built_packages {
-                        println!("Package: {}", built_package.package_path.display());
-                        println!("Size: {} bytes", built_package.size);
-                        println!("Checksum: {}", built_package.checksum);
-                        println!("Build time: {} seconds", built_package.build_duration);
-                        println!(); // Empty line between packages
+    match cli.command {
+        Command::Build {
+            spec,
+            verbose,
+            target,
+            all_targets,
+            output_dir,
+            no_bubblewrap,
+            skip_integrity,
+            no_deps,
+            clean,
+            needed,
+            sign,
+            manifest,
+            message_format,
+        } => {
+            let target_label = if all_targets {
+                "all".to_string()
+            } else {
+                target
+                    .as_ref()
+                    .map(|arch| arch.as_label().to_string())
+                    .unwrap_or_else(|| "host".to_string())
+            };
+            let write_manifest = manifest || sign.is_some();
+
+            match build_package(
+                &spec,
+                verbose,
+                target,
+                all_targets,
+                output_dir,
+                !no_bubblewrap,
+                skip_integrity,
+                no_deps,
+                clean,
+                needed,
+                sign,
+                write_manifest,
+                &cli.config,
+            ) {
+                Ok((built_packages, manifest_path)) => match message_format {
+                    MessageFormat::Human => {
+                        println!("Package built successfully!");
+                        for built_package in &built_packages {
+                            println!("Package: {}", built_package.package_path.display());
+                            println!("Size: {} bytes", built_package.size);
+                            println!("Checksum: {}", built_package.checksum);
+                            println!("Build time: {} seconds", built_package.build_duration);
+                            println!(); // Empty line between packages
+                        }
+                        if let Some(path) = &manifest_path {
+                            println!("Release manifest: {}", path.display());
+                        }
                     }
-                }
+                    MessageFormat::Json => {
+                        for built_package in &built_packages {
+                            println!(
+                                "{}",
+                                json!({
+                                    "package_path": built_package.package_path,
+                                    "size": built_package.size,
+                                    "checksum": built_package.checksum,
+                                    "build_duration": built_package.build_duration,
+                                    "target_arch": target_label,
+                                })
+                            );
+                        }
+                        if let Some(path) = &manifest_path {
+                            println!("{}", json!({ "manifest_path": path }));
+                        }
+                    }
+                },
                 Err(e) => {
-                    eprintln!("Build failed: {}", e);
+                    if message_format == MessageFormat::Json {
+                        println!("{}", json!({ "reason": "build-failed", "message": e }));
+                    } else {
+                        eprintln!("Build failed: {}", e);
+                    }
                     exit(1);
                 }
             }
         }
-        "validate" => {
-            if args.len() < 3 {
-                eprintln!("Error: Package specification file required");
-                print_usage();
-                exit(1);
-            }
-
-            let spec_path = Path::new(&args[2]);
-
-            match validate_spec(spec_path) {
-                Ok(errors) => {
-                    if errors.is_empty() {
+        Command::Validate {
+            spec,
+            message_format,
+        } => match validate_spec(&spec, &cli.config) {
+            Ok(errors) => {
+                if errors.is_empty() {
+                    if message_format == MessageFormat::Json {
+                        println!("[]");
+                    } else {
                         println!("Package specification is valid!");
+                    }
+                } else {
+                    if message_format == MessageFormat::Json {
+                        let structured: Vec<_> = errors
+                            .iter()
+                            .map(|message| {
+                                json!({
+                                    "field": classify_validation_error(message),
+                                    "message": message,
+                                })
+                            })
+                            .collect();
+                        println!("{}", json!(structured));
                     } else {
                         println!("Package specification has errors:");
                         for error in errors {
                             println!("  â€¢ {}", error);
                         }
-                        exit(1);
                     }
-                }
-                Err(e) => {
-                    eprintln!("Validation failed: {}", e);
                     exit(1);
                 }
             }
-        }
-        "init" => {
-            if args.len() < 3 {
-                eprintln!("Error: Package name required");
-                print_usage();
+            Err(e) => {
+                if message_format == MessageFormat::Json {
+                    println!("{}", json!({ "reason": "validation-failed", "message": e }));
+                } else {
+                    eprintln!("Validation failed: {}", e);
+                }
                 exit(1);
             }
+        },
+        Command::Init { name, output_dir } => {
+            let output_dir = output_dir.unwrap_or_else(|| PathBuf::from("."));
 
-            let package_name = &args[2];
-            let output_dir = args.get(3).map(|s| Path::new(s)).unwrap_or(Path::new("."));
-
-            match init_package(package_name, output_dir) {
+            match init_package(&name, &output_dir) {
                 Ok(_) => {
-                    println!("Package template created for: {}", package_name);
+                    println!("Package template created for: {}", name);
                     println!("Edit the pax.yaml file and run 'pax-builder build pax.yaml'");
                 }
                 Err(e) => {
@@ -102,7 +457,55 @@ fn main() {
                 }
             }
         }
-        "clean" => match clean_build_directory() {
+        Command::Bump {
+            component,
+            spec,
+            pre,
+        } => match bump_version(&spec, component.into(), pre.as_deref(), &cli.config) {
+            Ok(new_version) => println!("{}", new_version),
+            Err(e) => {
+                eprintln!("Bump failed: {}", e);
+                exit(1);
+            }
+        },
+        Command::Fetch { spec } => match fetch_sources(&spec, &cli.config) {
+            Ok(fetched) => {
+                println!("Fetched and verified {} source(s):", fetched.len());
+                for path in fetched {
+                    println!("  {}", path.display());
+                }
+            }
+            Err(e) => {
+                eprintln!("Fetch failed: {}", e);
+                exit(1);
+            }
+        },
+        Command::Phase { spec, from, to } => {
+            match run_phases(&spec, from.into(), to.into(), &cli.config) {
+                Ok(_) => println!(
+                    "Ran phases {} through {}",
+                    BuildPhase::from(from).as_label(),
+                    BuildPhase::from(to).as_label()
+                ),
+                Err(e) => {
+                    eprintln!("Phase run failed: {}", e);
+                    exit(1);
+                }
+            }
+        }
+        Command::Install { archive, root } => {
+            match install_package(archive, root.unwrap_or_else(|| PathBuf::from("/"))) {
+                Ok(files) => {
+                    println!("Package installed successfully!");
+                    println!("Files written: {}", files.len());
+                }
+                Err(e) => {
+                    eprintln!("Install failed: {}", e);
+                    exit(1);
+                }
+            }
+        }
+        Command::Clean => match clean_build_directory(&cli.config) {
             Ok(_) => {
                 println!("Build directory cleaned");
             }
@@ -111,45 +514,88 @@ fn main() {
                 exit(1);
             }
         },
-        "help" | "--help" | "-h" => {
-            print_usage();
-        }
-        _ => {
-            eprintln!("Error: Unknown command '{}'", command);
-            print_usage();
-            exit(1);
+        Command::Completions { shell } => {
+            let mut command = Cli::command();
+            let name = command.get_name().to_string();
+            generate(shell, &mut command, name, &mut io::stdout());
         }
     }
 }
 
-fn parse_target_flag(args: &[String]) -> Option<TargetArch> {
-    for (i, arg) in args.iter().enumerate() {
-        if arg == "--target" && i + 1 < args.len() {
-            return TargetArch::from_str(&args[i + 1]);
-        }
+/// Best-effort mapping from a `validate_spec` prose message back to the
+/// spec field it's about, for `--message-format=json`'s `{field, message}`
+/// records. `validate_spec` itself stays plain `Vec<String>` since that's
+/// what the rest of the codebase (and its tests) already expect.
+fn classify_validation_error(message: &str) -> &'static str {
+    if message.contains("name") {
+        "name"
+    } else if message.contains("Version") || message.contains("version") {
+        "version"
+    } else if message.contains("description") {
+        "description"
+    } else if message.contains("author") {
+        "author"
+    } else if message.contains("build command") {
+        "build.build_commands"
+    } else if message.contains("Install files") {
+        "install.install_files"
+    } else if message.contains("Install commands") {
+        "install.install_commands"
+    } else {
+        "spec"
     }
-    None
 }
 
-fn parse_output_dir_flag(args: &[String]) -> Option<PathBuf> {
-    for (i, arg) in args.iter().enumerate() {
-        if arg == "--output-dir" && i + 1 < args.len() {
-            return Some(PathBuf::from(&args[i + 1]));
+fn bump_version(
+    spec_path: &Path,
+    component: VersionComponent,
+    pre_release: Option<&str>,
+    config: &Option<PathBuf>,
+) -> Result<String, String> {
+    let builder = make_builder(config)?;
+    builder.bump_version(spec_path, component, pre_release)
+}
+
+fn install_package(archive_path: PathBuf, root: PathBuf) -> Result<Vec<PathBuf>, String> {
+    use std::sync::mpsc;
+
+    let installer = Installer::new_for_file(archive_path).with_root(root);
+    let (progress_tx, progress_rx) = mpsc::channel();
+
+    let worker = installer.install(progress_tx);
+
+    for message in progress_rx {
+        match message {
+            InstallMessage::ArchiveLen(len) => println!("Archive size: {} bytes", len),
+            InstallMessage::Extracting(path) => println!("Extracting: {}", path.display()),
+            InstallMessage::Finished => println!("Extraction complete"),
         }
     }
-    None
+
+    worker
+        .join()
+        .map_err(|_| "Install worker thread panicked".to_string())?
 }
 
 fn build_package(
     spec_path: &Path,
     verbose: bool,
     target_arch: Option<TargetArch>,
+    all_targets: bool,
     output_dir: Option<PathBuf>,
     use_bubblewrap: bool,
-) -> Result<Vec<BuiltPackage>, String> {
-    let mut builder = PaxPackageBuilder::new()?.with_verbose(verbose);
-
-    // Set target architecture if specified
+    skip_integrity: bool,
+    no_deps: bool,
+    clean: bool,
+    needed: bool,
+    sign_key: Option<String>,
+    write_manifest: bool,
+    config: &Option<PathBuf>,
+) -> Result<(Vec<BuiltPackage>, Option<PathBuf>), String> {
+    let mut builder = make_builder(config)?.with_verbose(verbose);
+
+    // Set target architecture if specified (ignored when building every
+    // target_architectures entry via --all-targets)
     if let Some(target) = target_arch {
         builder = builder.with_target_arch(target)?;
     }
@@ -162,6 +608,19 @@ fn build_package(
     // Set bubblewrap usage
     builder = builder.with_bubblewrap(use_bubblewrap);
 
+    // Set integrity verification policy
+    builder = builder.with_skip_integrity(skip_integrity);
+
+    // Set build-control flags
+    builder = builder
+        .with_skip_deps(no_deps)
+        .with_clean_build(clean)
+        .with_needed(needed);
+
+    if let Some(key) = sign_key {
+        builder = builder.with_signing_key(key);
+    }
+
     // Validate spec first
     let errors = builder.validate_spec(spec_path)?;
     if !errors.is_empty() {
@@ -171,11 +630,38 @@ fn build_package(
         ));
     }
 
-    builder.build_package(spec_path)
+    let built_packages = if all_targets {
+        builder.build_all_targets(spec_path)?
+    } else {
+        builder.build_package(spec_path)?
+    };
+
+    let manifest_path = if write_manifest {
+        Some(builder.write_release_manifest(&built_packages)?)
+    } else {
+        None
+    };
+
+    Ok((built_packages, manifest_path))
+}
+
+fn fetch_sources(spec_path: &Path, config: &Option<PathBuf>) -> Result<Vec<PathBuf>, String> {
+    let builder = make_builder(config)?;
+    builder.fetch_sources(spec_path)
 }
 
-fn validate_spec(spec_path: &Path) -> Result<Vec<String>, String> {
-    let builder = PaxPackageBuilder::new()?;
+fn run_phases(
+    spec_path: &Path,
+    from: BuildPhase,
+    to: BuildPhase,
+    config: &Option<PathBuf>,
+) -> Result<(), String> {
+    let mut builder = make_builder(config)?;
+    builder.run_phases(spec_path, from, to)
+}
+
+fn validate_spec(spec_path: &Path, config: &Option<PathBuf>) -> Result<Vec<String>, String> {
+    let builder = make_builder(config)?;
     builder.validate_spec(spec_path)
 }
 
@@ -198,6 +684,12 @@ categories:
   - development
   - tools
 
+# Uncomment to fetch and verify an upstream tarball before the build runs:
+# sources:
+#   - url: "https://example.com/package-1.0.0.tar.gz"
+#     sha256: "REPLACE_WITH_SHA256"
+#     extract: true
+
 dependencies:
   build_dependencies:
     - name: "gcc"
@@ -367,41 +859,7 @@ MIT
     Ok(())
 }
 
-fn clean_build_directory() -> Result<(), String> {
-    let builder = PaxPackageBuilder::new()?;
+fn clean_build_directory(config: &Option<PathBuf>) -> Result<(), String> {
+    let builder = make_builder(config)?;
     builder.clean_build_directory()
 }
-
-fn print_usage() {
-    println!(
-        r#"PAX Package Builder
-
-USAGE:
-    pax-builder <COMMAND> [OPTIONS]
-
-COMMANDS:
-    build <spec>     Build a package from a specification file
-    validate <spec>  Validate a package specification file
-    init <name>      Create a new package template
-    clean            Clean the build directory
-    help             Show this help message
-
-OPTIONS:
-    -v, --verbose        Enable verbose output
-    --target <arch>      Cross-compile for target architecture (x86_64v1, x86_64v3, aarch64, armv7l, riscv64, etc.)
-    --output-dir <dir>   Specify output directory for packages (default: current directory)
-    --no-bubblewrap      Disable bubblewrap build isolation
-
-EXAMPLES:
-    pax-builder init my-package
-    pax-builder validate pax.yaml
-    pax-builder build pax.yaml --verbose
-    pax-builder build pax.yaml --target x86_64v3
-    pax-builder build pax.yaml --output-dir ./packages
-    pax-builder build pax.yaml --no-bubblewrap
-    pax-builder clean
-
-For more information, visit: https://github.com/your-org/pax-rs
-"#
-    );
-}
@@ -1,19 +1,46 @@
-use axum::response::Html;
-use axum::{http::StatusCode, response::IntoResponse, routing::get, Router};
+use axum::extract::{Path as AxumPath, Query, State};
+use axum::response::{Html, Json};
+use axum::{body::Bytes, http::HeaderMap, http::StatusCode, response::IntoResponse, routing::{get, post}, Router};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
 use tokio::net::TcpListener;
+use tokio::sync::RwLock;
 use tower_http::services::ServeFile;
 
+mod version_checker;
+
+use version_checker::{PackageVersion, VersionChecker, VersionCompareOptions, VersionStatus};
+
+#[derive(Clone)]
+struct AppState {
+    versions: Arc<RwLock<Vec<PackageVersion>>>,
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let port = std::env::var("PORT").unwrap_or_else(|_| "8081".to_string());
     let addr = SocketAddr::from(([0, 0, 0, 0], port.parse()?));
 
+    let initial_versions = VersionChecker::check_all_packages(&recipes_dir())
+        .await
+        .unwrap_or_default();
+    let state = AppState {
+        versions: Arc::new(RwLock::new(initial_versions)),
+    };
+
     let app = Router::new()
         .route("/healthz", get(healthz))
         .route("/", get(dashboard_page))
+        .route("/api/versions", get(api_versions))
+        .route("/api/versions/:package", get(api_version_for_package))
+        .route("/webhook/github", post(github_webhook))
         .route_service("/OBS.png", ServeFile::new("OBS.png"))
-        .fallback(fallback_404);
+        .fallback(fallback_404)
+        .with_state(state);
 
     println!("OBS Read-only Dashboard listening on http://{}", addr);
 
@@ -31,6 +58,163 @@ async fn dashboard_page() -> impl IntoResponse {
     Html(include_str!("static/obs_public.html"))
 }
 
+fn recipes_dir() -> PathBuf {
+    std::env::var("RECIPES_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("./recipes"))
+}
+
+fn matches_status_filter(version: &PackageVersion, filter: &str) -> bool {
+    let status_name = match version.status {
+        VersionStatus::UpToDate => "up_to_date",
+        VersionStatus::UpdateAvailable => "update_available",
+        VersionStatus::Unknown => "unknown",
+        VersionStatus::Error => "error",
+    };
+    status_name.eq_ignore_ascii_case(filter)
+}
+
+/// `GET /api/versions?status=update_available` — structured version data for
+/// every recipe, so external tooling doesn't have to scrape the HTML page.
+/// Served from the in-memory cache the webhook keeps warm.
+async fn api_versions(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> impl IntoResponse {
+    let versions = state.versions.read().await;
+
+    let filtered: Vec<&PackageVersion> = match params.get("status") {
+        Some(status) => versions
+            .iter()
+            .filter(|v| matches_status_filter(v, status))
+            .collect(),
+        None => versions.iter().collect(),
+    };
+
+    (
+        StatusCode::OK,
+        [("Cache-Control", "max-age=60")],
+        Json(filtered),
+    )
+        .into_response()
+}
+
+/// `GET /api/versions/{package}` — version data for a single recipe.
+async fn api_version_for_package(
+    State(state): State<AppState>,
+    AxumPath(package): AxumPath<String>,
+) -> impl IntoResponse {
+    let versions = state.versions.read().await;
+
+    match versions.iter().find(|v| v.name == package) {
+        Some(version) => (
+            StatusCode::OK,
+            [("Cache-Control", "max-age=60")],
+            Json(version),
+        )
+            .into_response(),
+        None => (StatusCode::NOT_FOUND, "Package not found").into_response(),
+    }
+}
+
+/// `POST /webhook/github` — GitHub `push`/`release` events, verified with
+/// `X-Hub-Signature-256` (HMAC-SHA256 over the raw body, shared secret from
+/// `GITHUB_WEBHOOK_SECRET`). On a `release` event, updates just the matching
+/// recipe's cached `PackageVersion` instead of re-scanning every recipe.
+async fn github_webhook(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    let secret = match std::env::var("GITHUB_WEBHOOK_SECRET") {
+        Ok(secret) => secret,
+        Err(_) => return (StatusCode::UNAUTHORIZED, "Webhook secret not configured").into_response(),
+    };
+
+    let signature_header = match headers
+        .get("X-Hub-Signature-256")
+        .and_then(|v| v.to_str().ok())
+    {
+        Some(header) => header,
+        None => return (StatusCode::UNAUTHORIZED, "Missing signature").into_response(),
+    };
+
+    if !verify_signature(&secret, &body, signature_header) {
+        return (StatusCode::UNAUTHORIZED, "Signature mismatch").into_response();
+    }
+
+    let event: serde_json::Value = match serde_json::from_slice(&body) {
+        Ok(event) => event,
+        Err(_) => return (StatusCode::BAD_REQUEST, "Invalid JSON payload").into_response(),
+    };
+
+    let (Some(repo_url), Some(tag_name)) = (
+        event
+            .get("repository")
+            .and_then(|r| r.get("html_url"))
+            .and_then(|v| v.as_str()),
+        event
+            .get("release")
+            .and_then(|r| r.get("tag_name"))
+            .and_then(|v| v.as_str()),
+    ) else {
+        // Not a release event (e.g. a push event) — accept and ignore.
+        return StatusCode::NO_CONTENT.into_response();
+    };
+
+    let upstream_version = tag_name.trim_start_matches('v').to_string();
+    let mut versions = state.versions.write().await;
+    let mut updated = false;
+    for version in versions.iter_mut() {
+        if version.upstream_url.as_deref() == Some(repo_url) {
+            version.upstream_version = Some(upstream_version.clone());
+            let (status, comparison_reliable) = VersionChecker::resolve_status(
+                &version.current_version,
+                Some(&upstream_version),
+                &VersionCompareOptions::default(),
+            );
+            version.status = status;
+            version.comparison_reliable = comparison_reliable;
+            updated = true;
+        }
+    }
+
+    if updated {
+        StatusCode::NO_CONTENT.into_response()
+    } else {
+        (StatusCode::OK, "No matching recipe for this repository").into_response()
+    }
+}
+
+/// Compute `sha256=<hex hmac>` over `body` with `secret` and compare it
+/// against `signature_header` in constant time.
+fn verify_signature(secret: &str, body: &[u8], signature_header: &str) -> bool {
+    let mut mac = match Hmac::<Sha256>::new_from_slice(secret.as_bytes()) {
+        Ok(mac) => mac,
+        Err(_) => return false,
+    };
+    mac.update(body);
+    let computed = mac.finalize().into_bytes();
+    let computed_hex = format!("sha256={}", hex_encode(&computed));
+
+    constant_time_eq(computed_hex.as_bytes(), signature_header.as_bytes())
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut diff = 0u8;
+    for (x, y) in a.iter().zip(b.iter()) {
+        diff |= x ^ y;
+    }
+    diff == 0
+}
+
 async fn fallback_404() -> impl IntoResponse {
     (StatusCode::NOT_FOUND, "Not Found")
 }
@@ -0,0 +1,322 @@
+use serde_json::json;
+use std::path::Path;
+
+use crate::version_checker::PackageVersion;
+
+/// Opens (or updates) a pull request bumping a recipe's `version:` field to
+/// match a detected upstream release.
+///
+/// Everything here is gated by `token` being present and `dry_run` being
+/// false — with no token, or in dry-run mode, `bump_recipe` only logs what it
+/// would have done.
+pub struct PrBumper {
+    /// `owner/repo` of the recipes repository the PRs are opened against.
+    pub repo_slug: String,
+    pub token: Option<String>,
+    pub dry_run: bool,
+}
+
+impl PrBumper {
+    pub fn new(repo_slug: String, token: Option<String>, dry_run: bool) -> Self {
+        Self {
+            repo_slug,
+            token,
+            dry_run,
+        }
+    }
+
+    /// Bump `recipe_dir/pax.yaml` to `version.upstream_version` on a fresh
+    /// branch and open (or update) a pull request for it. `repo_relative_path`
+    /// is `recipe_dir/pax.yaml`'s path relative to the recipes repo root
+    /// (e.g. `foo/pax.yaml`), used for the GitHub Contents API calls — it
+    /// must not be collapsed to just the file name, or the commit lands at
+    /// the repo root instead of inside the package's own directory.
+    pub async fn bump_recipe(
+        &self,
+        recipe_dir: &Path,
+        repo_relative_path: &Path,
+        version: &PackageVersion,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let new_version = version
+            .upstream_version
+            .as_ref()
+            .ok_or("No upstream version to bump to")?;
+
+        let yaml_path = recipe_dir.join("pax.yaml");
+        let original = std::fs::read_to_string(&yaml_path)?;
+        let updated = Self::rewrite_version_field(&original, new_version)?;
+
+        let branch_name = format!("bump-{}-{}", version.name, new_version);
+        let title = format!("Update {} to {}", version.name, new_version);
+
+        if self.dry_run || self.token.is_none() {
+            println!(
+                "[dry-run] Would open PR '{}' on branch '{}' for {}",
+                title,
+                branch_name,
+                yaml_path.display()
+            );
+            return Ok(branch_name);
+        }
+
+        let token = self.token.as_deref().unwrap();
+        let client = reqwest::Client::new();
+
+        let existing_pr = self.find_open_pr(&client, token, &branch_name).await?;
+
+        let (default_branch, default_branch_sha) = self.default_branch(&client, token).await?;
+        self.update_branch_ref(&client, token, &branch_name, &default_branch_sha)
+            .await?;
+        self.commit_file(
+            &client,
+            token,
+            &branch_name,
+            repo_relative_path,
+            &updated,
+            &format!("Update {} to {}", version.name, new_version),
+        )
+        .await?;
+
+        match existing_pr {
+            Some(pr_number) => {
+                self.update_pr(&client, token, pr_number, &title).await?;
+            }
+            None => {
+                self.open_pr(&client, token, &branch_name, &title, &default_branch)
+                    .await?;
+            }
+        }
+
+        Ok(branch_name)
+    }
+
+    /// Rewrite only the top-level `version: "..."` line, leaving the rest of
+    /// the YAML document untouched.
+    fn rewrite_version_field(
+        yaml: &str,
+        new_version: &str,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let mut found = false;
+        let rewritten: Vec<String> = yaml
+            .lines()
+            .map(|line| {
+                if !found && line.starts_with("version:") {
+                    found = true;
+                    format!("version: \"{}\"", new_version)
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect();
+
+        if !found {
+            return Err("No version: field found in pax.yaml".into());
+        }
+
+        Ok(rewritten.join("\n") + "\n")
+    }
+
+    async fn find_open_pr(
+        &self,
+        client: &reqwest::Client,
+        token: &str,
+        branch_name: &str,
+    ) -> Result<Option<u64>, Box<dyn std::error::Error>> {
+        let (owner, _) = self.split_slug()?;
+        let url = format!(
+            "https://api.github.com/repos/{}/pulls?head={}:{}&state=open",
+            self.repo_slug, owner, branch_name
+        );
+        let response = client
+            .get(&url)
+            .header("User-Agent", "pax-builder")
+            .header("Authorization", format!("Bearer {}", token))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Ok(None);
+        }
+
+        let pulls: Vec<serde_json::Value> = response.json().await?;
+        Ok(pulls
+            .first()
+            .and_then(|pr| pr.get("number"))
+            .and_then(|n| n.as_u64()))
+    }
+
+    /// Resolve the repository's default branch (e.g. `main`, `master`,
+    /// `trunk`) and the sha it currently points at.
+    async fn default_branch(
+        &self,
+        client: &reqwest::Client,
+        token: &str,
+    ) -> Result<(String, String), Box<dyn std::error::Error>> {
+        let repo_url = format!("https://api.github.com/repos/{}", self.repo_slug);
+        let repo: serde_json::Value = client
+            .get(&repo_url)
+            .header("User-Agent", "pax-builder")
+            .header("Authorization", format!("Bearer {}", token))
+            .send()
+            .await?
+            .json()
+            .await?;
+        let default_branch = repo
+            .get("default_branch")
+            .and_then(|v| v.as_str())
+            .unwrap_or("main")
+            .to_string();
+
+        let ref_url = format!(
+            "https://api.github.com/repos/{}/git/ref/heads/{}",
+            self.repo_slug, default_branch
+        );
+        let ref_doc: serde_json::Value = client
+            .get(&ref_url)
+            .header("User-Agent", "pax-builder")
+            .header("Authorization", format!("Bearer {}", token))
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        let sha = ref_doc
+            .get("object")
+            .and_then(|o| o.get("sha"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .ok_or("Failed to resolve default branch sha")?;
+
+        Ok((default_branch, sha))
+    }
+
+    async fn update_branch_ref(
+        &self,
+        client: &reqwest::Client,
+        token: &str,
+        branch_name: &str,
+        sha: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let url = format!(
+            "https://api.github.com/repos/{}/git/refs",
+            self.repo_slug
+        );
+        let _ = client
+            .post(&url)
+            .header("User-Agent", "pax-builder")
+            .header("Authorization", format!("Bearer {}", token))
+            .json(&json!({ "ref": format!("refs/heads/{}", branch_name), "sha": sha }))
+            .send()
+            .await;
+
+        let update_url = format!(
+            "https://api.github.com/repos/{}/git/refs/heads/{}",
+            self.repo_slug, branch_name
+        );
+        client
+            .patch(&update_url)
+            .header("User-Agent", "pax-builder")
+            .header("Authorization", format!("Bearer {}", token))
+            .json(&json!({ "sha": sha, "force": true }))
+            .send()
+            .await?;
+
+        Ok(())
+    }
+
+    async fn commit_file(
+        &self,
+        client: &reqwest::Client,
+        token: &str,
+        branch_name: &str,
+        repo_relative_path: &Path,
+        contents: &str,
+        message: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        use base64::Engine;
+
+        let repo_relative_path = repo_relative_path.to_string_lossy().replace('\\', "/");
+
+        let url = format!(
+            "https://api.github.com/repos/{}/contents/{}",
+            self.repo_slug, repo_relative_path
+        );
+        let existing: serde_json::Value = client
+            .get(format!("{}?ref={}", url, branch_name))
+            .header("User-Agent", "pax-builder")
+            .header("Authorization", format!("Bearer {}", token))
+            .send()
+            .await?
+            .json()
+            .await
+            .unwrap_or(serde_json::Value::Null);
+
+        let existing_sha = existing.get("sha").and_then(|v| v.as_str());
+        let encoded = base64::engine::general_purpose::STANDARD.encode(contents.as_bytes());
+
+        let mut payload = json!({
+            "message": message,
+            "content": encoded,
+            "branch": branch_name,
+        });
+        if let Some(sha) = existing_sha {
+            payload["sha"] = json!(sha);
+        }
+
+        client
+            .put(&url)
+            .header("User-Agent", "pax-builder")
+            .header("Authorization", format!("Bearer {}", token))
+            .json(&payload)
+            .send()
+            .await?;
+
+        Ok(())
+    }
+
+    async fn open_pr(
+        &self,
+        client: &reqwest::Client,
+        token: &str,
+        branch_name: &str,
+        title: &str,
+        base_branch: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let url = format!("https://api.github.com/repos/{}/pulls", self.repo_slug);
+        client
+            .post(&url)
+            .header("User-Agent", "pax-builder")
+            .header("Authorization", format!("Bearer {}", token))
+            .json(&json!({ "title": title, "head": branch_name, "base": base_branch }))
+            .send()
+            .await?;
+        Ok(())
+    }
+
+    async fn update_pr(
+        &self,
+        client: &reqwest::Client,
+        token: &str,
+        pr_number: u64,
+        title: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let url = format!(
+            "https://api.github.com/repos/{}/pulls/{}",
+            self.repo_slug, pr_number
+        );
+        client
+            .patch(&url)
+            .header("User-Agent", "pax-builder")
+            .header("Authorization", format!("Bearer {}", token))
+            .json(&json!({ "title": title }))
+            .send()
+            .await?;
+        Ok(())
+    }
+
+    fn split_slug(&self) -> Result<(&str, &str), Box<dyn std::error::Error>> {
+        self.repo_slug
+            .split_once('/')
+            .ok_or_else(|| "repo_slug must be owner/repo".into())
+    }
+}
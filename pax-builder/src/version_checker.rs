@@ -8,9 +8,40 @@ pub struct PackageVersion {
     pub upstream_version: Option<String>,
     pub upstream_url: Option<String>,
     pub status: VersionStatus,
+    /// Where `upstream_version` was resolved from. Defaults to `Git` for the
+    /// existing forge-release path.
+    #[serde(default)]
+    pub upstream_source: UpstreamSource,
+    /// Whether `status` was decided by parsing both versions as semver.
+    /// `false` means at least one side didn't parse and we fell back to the
+    /// old raw string comparison, or the check was rate limited (`status` is
+    /// then `Error`), so the dashboard should flag it as unreliable.
+    #[serde(default = "default_comparison_reliable")]
+    pub comparison_reliable: bool,
+    /// Set when `status` is `Error`, e.g. a rate-limit message.
+    #[serde(default)]
+    pub error_message: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+fn default_comparison_reliable() -> bool {
+    true
+}
+
+/// Options controlling how `current_version` and the resolved upstream
+/// version are compared, read from a recipe's `pax.yaml`.
+#[derive(Debug, Clone, Default)]
+pub struct VersionCompareOptions {
+    /// Skip upstream tags whose semver has a non-empty pre-release segment.
+    pub ignore_prereleases: bool,
+    /// Strip this literal prefix (e.g. a project name) from both versions
+    /// before parsing.
+    pub version_prefix: Option<String>,
+    /// A regex whose first capture group is the semver-parseable portion of
+    /// the tag, for stripping date suffixes or other tag noise.
+    pub version_regex: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum VersionStatus {
     UpToDate,
     UpdateAvailable,
@@ -18,6 +49,83 @@ pub enum VersionStatus {
     Error,
 }
 
+/// Which kind of upstream `PackageVersion::upstream_version` was resolved
+/// from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum UpstreamSource {
+    #[default]
+    Git,
+    DistroRepository,
+}
+
+/// Which forge a `repo_url` (or an explicit `forge_type` override) belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ForgeType {
+    GitHub,
+    GitLab,
+    Gitea,
+}
+
+impl ForgeType {
+    /// Detect the forge from the host portion of a repository URL.
+    ///
+    /// Self-hosted Gitea/Forgejo instances don't have a recognizable host, so
+    /// callers that know their recipe points at one should fall back to the
+    /// `forge_type` field in `pax.yaml` instead of relying on detection.
+    pub fn detect(repo_url: &str) -> Option<Self> {
+        let host = repo_url
+            .trim_start_matches("https://")
+            .trim_start_matches("http://")
+            .split('/')
+            .next()?;
+
+        if host == "github.com" {
+            Some(ForgeType::GitHub)
+        } else if host == "gitlab.com" || host.starts_with("gitlab.") {
+            Some(ForgeType::GitLab)
+        } else if host.starts_with("gitea.") || host.starts_with("codeberg.") {
+            Some(ForgeType::Gitea)
+        } else {
+            None
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "github" => Some(ForgeType::GitHub),
+            "gitlab" => Some(ForgeType::GitLab),
+            "gitea" | "forgejo" => Some(ForgeType::Gitea),
+            _ => None,
+        }
+    }
+}
+
+/// A cached GitHub API response, keyed by `{owner}/{repo}/{endpoint}` in the
+/// on-disk cache file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct GithubCacheEntry {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    body: String,
+}
+
+/// Returned when GitHub responds with a rate-limit status. Carries the
+/// cached version (if one was available) so callers can keep showing a value
+/// while still surfacing that the check didn't actually refresh.
+#[derive(Debug)]
+struct RateLimitedError {
+    message: String,
+    cached_version: Option<String>,
+}
+
+impl std::fmt::Display for RateLimitedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for RateLimitedError {}
+
 pub struct VersionChecker;
 
 impl VersionChecker {
@@ -26,16 +134,53 @@ impl VersionChecker {
         current_version: &str,
         repo_url: Option<&str>,
     ) -> Result<PackageVersion, Box<dyn std::error::Error>> {
+        Self::check_package_version_with_forge(package_name, current_version, repo_url, None)
+            .await
+    }
+
+    pub async fn check_package_version_with_forge(
+        package_name: &str,
+        current_version: &str,
+        repo_url: Option<&str>,
+        forge_type: Option<ForgeType>,
+    ) -> Result<PackageVersion, Box<dyn std::error::Error>> {
+        Self::check_package_version_with_options(
+            package_name,
+            current_version,
+            repo_url,
+            forge_type,
+            &VersionCompareOptions::default(),
+        )
+        .await
+    }
+
+    pub async fn check_package_version_with_options(
+        package_name: &str,
+        current_version: &str,
+        repo_url: Option<&str>,
+        forge_type: Option<ForgeType>,
+        options: &VersionCompareOptions,
+    ) -> Result<PackageVersion, Box<dyn std::error::Error>> {
+        let mut rate_limit_message = None;
         let upstream_version = if let Some(repo_url) = repo_url {
-            Self::fetch_upstream_version(repo_url).await.ok()
+            match Self::fetch_upstream_version_for_forge(repo_url, forge_type).await {
+                Ok(version) => Some(version),
+                Err(err) => match err.downcast::<RateLimitedError>() {
+                    Ok(rate_limited) => {
+                        rate_limit_message = Some(rate_limited.message.clone());
+                        rate_limited.cached_version.clone()
+                    }
+                    Err(_) => None,
+                },
+            }
         } else {
             None
         };
 
-        let status = match &upstream_version {
-            Some(upstream) if upstream != current_version => VersionStatus::UpdateAvailable,
-            Some(_) => VersionStatus::UpToDate,
-            None => VersionStatus::Unknown,
+        let (status, comparison_reliable) = if rate_limit_message.is_some() {
+            (VersionStatus::Error, false)
+        } else {
+            Self::resolve_status(current_version, upstream_version.as_deref(), options)
         };
 
         Ok(PackageVersion {
@@ -44,16 +189,190 @@ impl VersionChecker {
             upstream_version,
             upstream_url: repo_url.map(|s| s.to_string()),
             status,
+            upstream_source: UpstreamSource::Git,
+            comparison_reliable,
+            error_message: rate_limit_message,
+        })
+    }
+
+    /// Decide a `VersionStatus` for `current` vs `upstream`.
+    ///
+    /// Both strings are normalized (prefix/regex stripped) and parsed as
+    /// semver. `UpdateAvailable` is only returned when the upstream version
+    /// parses strictly greater than the current one; a prerelease upstream is
+    /// skipped (treated as no update) when `options.ignore_prereleases` is
+    /// set. When either side fails to parse, falls back to the old raw
+    /// string-inequality check and reports the comparison as unreliable.
+    pub(crate) fn resolve_status(
+        current: &str,
+        upstream: Option<&str>,
+        options: &VersionCompareOptions,
+    ) -> (VersionStatus, bool) {
+        let upstream = match upstream {
+            Some(u) => u,
+            None => return (VersionStatus::Unknown, true),
+        };
+
+        let normalized_current = Self::normalize_version_tag(current, options);
+        let normalized_upstream = Self::normalize_version_tag(upstream, options);
+
+        match (
+            semver::Version::parse(&normalized_current),
+            semver::Version::parse(&normalized_upstream),
+        ) {
+            (Ok(current_semver), Ok(upstream_semver)) => {
+                if options.ignore_prereleases && !upstream_semver.pre.is_empty() {
+                    return (VersionStatus::UpToDate, true);
+                }
+                if upstream_semver > current_semver {
+                    (VersionStatus::UpdateAvailable, true)
+                } else {
+                    (VersionStatus::UpToDate, true)
+                }
+            }
+            _ => {
+                let status = if upstream != current {
+                    VersionStatus::UpdateAvailable
+                } else {
+                    VersionStatus::UpToDate
+                };
+                (status, false)
+            }
+        }
+    }
+
+    /// Strip common tag noise (leading `v`, a configured literal prefix, or a
+    /// configured regex capture) before handing a tag to the semver parser.
+    fn normalize_version_tag(tag: &str, options: &VersionCompareOptions) -> String {
+        let mut normalized = tag.trim();
+
+        if let Some(prefix) = &options.version_prefix {
+            if let Some(stripped) = normalized.strip_prefix(prefix.as_str()) {
+                normalized = stripped;
+            }
+        }
+
+        if let Some(pattern) = &options.version_regex {
+            if let Ok(re) = regex::Regex::new(pattern) {
+                if let Some(captures) = re.captures(normalized) {
+                    if let Some(matched) = captures.get(1).or_else(|| captures.get(0)) {
+                        return matched.as_str().trim_start_matches('v').to_string();
+                    }
+                }
+            }
+        }
+
+        normalized.trim_start_matches('v').to_string()
+    }
+
+    /// Resolve a package's upstream version from a distro package index
+    /// instead of a Git forge, for upstreams that don't tag releases at all.
+    pub async fn check_package_version_from_distro_repo(
+        package_name: &str,
+        current_version: &str,
+        alpine_package: Option<&str>,
+        branch: &str,
+    ) -> Result<PackageVersion, Box<dyn std::error::Error>> {
+        let lookup_name = alpine_package.unwrap_or(package_name);
+        let upstream_version = Self::fetch_alpine_package_version(lookup_name, branch)
+            .await
+            .ok();
+
+        let (status, comparison_reliable) = Self::resolve_status(
+            current_version,
+            upstream_version.as_deref(),
+            &VersionCompareOptions::default(),
+        );
+
+        Ok(PackageVersion {
+            name: package_name.to_string(),
+            current_version: current_version.to_string(),
+            upstream_version,
+            upstream_url: Some(format!(
+                "https://pkgs.alpinelinux.org/packages?name={}&branch={}",
+                lookup_name, branch
+            )),
+            status,
+            upstream_source: UpstreamSource::DistroRepository,
+            comparison_reliable,
+            error_message: None,
         })
     }
 
+    /// Scrape `pkgs.alpinelinux.org`'s package index for `package`, returning
+    /// the packaged version if every listed architecture agrees, or an error
+    /// if they diverge (which means the index is mid-rebuild or the package
+    /// name matched something unrelated).
+    pub async fn fetch_alpine_package_version(
+        package: &str,
+        branch: &str,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let url = format!(
+            "https://pkgs.alpinelinux.org/packages?name={}&branch={}",
+            package, branch
+        );
+
+        let client = reqwest::Client::new();
+        let response = client
+            .get(&url)
+            .header("User-Agent", "pax-builder")
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(format!("Failed to fetch package index: HTTP {}", response.status()).into());
+        }
+
+        let body = response.text().await?;
+        let document = scraper::Html::parse_document(&body);
+        let row_selector = scraper::Selector::parse("table tr").map_err(|_| "Invalid selector")?;
+        let cell_selector = scraper::Selector::parse("td").map_err(|_| "Invalid selector")?;
+
+        let mut versions = std::collections::HashSet::new();
+        for row in document.select(&row_selector) {
+            let cells: Vec<String> = row
+                .select(&cell_selector)
+                .map(|cell| cell.text().collect::<String>().trim().to_string())
+                .collect();
+
+            // The package index table is laid out as
+            // branch | repo | arch | package | version | ...
+            if cells.len() > 4 && cells[3].eq_ignore_ascii_case(package) {
+                versions.insert(cells[4].clone());
+            }
+        }
+
+        match versions.len() {
+            0 => Err(format!("Package {} not found in index", package).into()),
+            1 => Ok(versions.into_iter().next().unwrap()),
+            _ => Err(format!(
+                "Package {} has diverging versions across architectures: {:?}",
+                package, versions
+            )
+            .into()),
+        }
+    }
+
     pub async fn fetch_upstream_version(
         repo_url: &str,
     ) -> Result<String, Box<dyn std::error::Error>> {
-        if repo_url.contains("github.com") {
-            Self::fetch_github_latest_tag(repo_url).await
-        } else {
-            Err("Unsupported repository type".into())
+        Self::fetch_upstream_version_for_forge(repo_url, None).await
+    }
+
+    /// Fetch the latest release tag from whichever forge hosts `repo_url`.
+    ///
+    /// The forge is detected from the URL's host when possible; `forge_type`
+    /// lets a recipe override that for self-hosted Gitea/Forgejo instances
+    /// that don't carry a recognizable hostname.
+    pub async fn fetch_upstream_version_for_forge(
+        repo_url: &str,
+        forge_type: Option<ForgeType>,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        match forge_type.or_else(|| ForgeType::detect(repo_url)) {
+            Some(ForgeType::GitHub) => Self::fetch_github_latest_tag(repo_url).await,
+            Some(ForgeType::GitLab) => Self::fetch_gitlab_latest_tag(repo_url).await,
+            Some(ForgeType::Gitea) => Self::fetch_gitea_latest_tag(repo_url).await,
+            None => Err("Unsupported repository type".into()),
         }
     }
 
@@ -79,6 +398,165 @@ impl VersionChecker {
             "https://api.github.com/repos/{}/{}/releases/latest",
             owner, repo
         );
+        let cache_key = format!("{}/{}/releases/latest", owner, repo);
+
+        let mut cache = Self::load_github_cache();
+        let cached_entry = cache.get(&cache_key).cloned();
+
+        let client = reqwest::Client::new();
+        let mut request = client.get(&api_url).header("User-Agent", "pax-builder");
+
+        if let Some(entry) = &cached_entry {
+            if let Some(etag) = &entry.etag {
+                request = request.header("If-None-Match", etag.clone());
+            }
+            if let Some(last_modified) = &entry.last_modified {
+                request = request.header("If-Modified-Since", last_modified.clone());
+            }
+        }
+        if let Ok(token) = std::env::var("GITHUB_TOKEN") {
+            if !token.is_empty() {
+                request = request.header("Authorization", format!("Bearer {}", token));
+            }
+        }
+
+        let response = request.send().await?;
+
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            if let Some(entry) = cached_entry {
+                return Self::tag_from_release_body(&entry.body);
+            }
+            return Err("Received 304 Not Modified but no cached response exists".into());
+        }
+
+        if response.status() == reqwest::StatusCode::FORBIDDEN
+            || response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS
+        {
+            let reset_message = response
+                .headers()
+                .get("X-RateLimit-Reset")
+                .and_then(|v| v.to_str().ok())
+                .map(|s| format!("rate limited, retry after {}", s))
+                .unwrap_or_else(|| "rate limited by GitHub".to_string());
+
+            return Err(Box::new(RateLimitedError {
+                message: reset_message,
+                cached_version: match &cached_entry {
+                    Some(entry) => Self::tag_from_release_body(&entry.body).ok(),
+                    None => None,
+                },
+            }));
+        }
+
+        if response.status().is_success() {
+            let etag = response
+                .headers()
+                .get("ETag")
+                .and_then(|v| v.to_str().ok())
+                .map(|s| s.to_string());
+            let last_modified = response
+                .headers()
+                .get("Last-Modified")
+                .and_then(|v| v.to_str().ok())
+                .map(|s| s.to_string());
+            let body = response.text().await?;
+
+            let version = Self::tag_from_release_body(&body)?;
+
+            cache.insert(
+                cache_key,
+                GithubCacheEntry {
+                    etag,
+                    last_modified,
+                    body,
+                },
+            );
+            Self::save_github_cache(&cache);
+
+            return Ok(version);
+        }
+
+        Err("No release found".into())
+    }
+
+    fn tag_from_release_body(body: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let release: serde_json::Value = serde_json::from_str(body)?;
+        release
+            .get("tag_name")
+            .and_then(|v| v.as_str())
+            .map(|tag| tag.trim_start_matches('v').to_string())
+            .ok_or_else(|| "No release found".into())
+    }
+
+    fn github_cache_path() -> std::path::PathBuf {
+        let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+        std::path::PathBuf::from(home).join(".local/share/pax-builder/github_cache.json")
+    }
+
+    fn load_github_cache() -> HashMap<String, GithubCacheEntry> {
+        let path = Self::github_cache_path();
+        std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_github_cache(cache: &HashMap<String, GithubCacheEntry>) {
+        let path = Self::github_cache_path();
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Ok(serialized) = serde_json::to_string_pretty(cache) {
+            let _ = std::fs::write(&path, serialized);
+        }
+    }
+
+    /// Fetch the latest release tag from a Gitea/Forgejo instance.
+    ///
+    /// Gitea's release object has the same shape as GitHub's (`tag_name`,
+    /// `draft`, `prerelease`, `published_at`), so the parsing mirrors
+    /// `fetch_github_latest_tag`.
+    pub async fn fetch_gitea_latest_tag(
+        repo_url: &str,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let (base, owner, repo) = Self::split_forge_url(repo_url)?;
+
+        let api_url = format!(
+            "{}/api/v1/repos/{}/{}/releases?limit=1",
+            base, owner, repo
+        );
+
+        let client = reqwest::Client::new();
+        let response = client
+            .get(&api_url)
+            .header("User-Agent", "pax-builder")
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            let releases: Vec<serde_json::Value> = response.json().await?;
+            if let Some(tag_name) = releases
+                .first()
+                .and_then(|release| release.get("tag_name"))
+                .and_then(|v| v.as_str())
+            {
+                return Ok(tag_name.trim_start_matches('v').to_string());
+            }
+        }
+
+        Err("No release found".into())
+    }
+
+    /// Fetch the latest release tag from a GitLab project (gitlab.com or
+    /// self-hosted).
+    pub async fn fetch_gitlab_latest_tag(
+        repo_url: &str,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let (base, owner, repo) = Self::split_forge_url(repo_url)?;
+        let project_path = format!("{}/{}", owner, repo);
+        let encoded_path = Self::url_encode_path(&project_path);
+
+        let api_url = format!("{}/api/v4/projects/{}/releases", base, encoded_path);
 
         let client = reqwest::Client::new();
         let response = client
@@ -88,16 +566,65 @@ impl VersionChecker {
             .await?;
 
         if response.status().is_success() {
-            let release: serde_json::Value = response.json().await?;
-            if let Some(tag_name) = release.get("tag_name").and_then(|v| v.as_str()) {
-                let version = tag_name.trim_start_matches('v');
-                return Ok(version.to_string());
+            let releases: Vec<serde_json::Value> = response.json().await?;
+            if let Some(tag_name) = releases
+                .first()
+                .and_then(|release| release.get("tag_name"))
+                .and_then(|v| v.as_str())
+            {
+                return Ok(tag_name.trim_start_matches('v').to_string());
             }
         }
 
         Err("No release found".into())
     }
 
+    /// Split a repository URL into its `(base_url, owner, repo)` components,
+    /// e.g. `https://gitlab.example.com/group/project` becomes
+    /// `("https://gitlab.example.com", "group", "project")`.
+    fn split_forge_url(
+        repo_url: &str,
+    ) -> Result<(String, String, String), Box<dyn std::error::Error>> {
+        let scheme = if repo_url.starts_with("http://") {
+            "http://"
+        } else {
+            "https://"
+        };
+        let without_scheme = repo_url
+            .trim_start_matches("https://")
+            .trim_start_matches("http://");
+        let without_suffix = without_scheme.strip_suffix(".git").unwrap_or(without_scheme);
+
+        let mut parts = without_suffix.splitn(2, '/');
+        let host = parts.next().ok_or("Invalid repository URL")?;
+        let path = parts.next().ok_or("Invalid repository URL")?;
+
+        let path_parts: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        if path_parts.len() < 2 {
+            return Err("Invalid repository URL".into());
+        }
+
+        let repo = path_parts[path_parts.len() - 1];
+        let owner = path_parts[..path_parts.len() - 1].join("/");
+
+        Ok((format!("{}{}", scheme, host), owner, repo.to_string()))
+    }
+
+    /// Percent-encode a project path for GitLab's `/projects/:id` endpoint,
+    /// which requires `/` to be escaped as `%2F`.
+    fn url_encode_path(path: &str) -> String {
+        let mut encoded = String::with_capacity(path.len());
+        for byte in path.bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                    encoded.push(byte as char)
+                }
+                _ => encoded.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+        encoded
+    }
+
     async fn fetch_github_releases(
         repo_url: &str,
     ) -> Result<Vec<String>, Box<dyn std::error::Error>> {
@@ -174,10 +701,53 @@ impl VersionChecker {
                             .and_then(|v| v.as_str())
                             .unwrap_or("unknown");
 
-                        if let Ok(version_info) =
-                            Self::check_package_version(&package_name, current_version, repo_url)
-                                .await
-                        {
+                        let forge_type = spec
+                            .get("forge_type")
+                            .and_then(|v| v.as_str())
+                            .and_then(ForgeType::from_str);
+
+                        let alpine_package = spec
+                            .get("alpine_package")
+                            .or_else(|| spec.get("repology_project"))
+                            .and_then(|v| v.as_str());
+
+                        let compare_options = VersionCompareOptions {
+                            ignore_prereleases: spec
+                                .get("ignore_prereleases")
+                                .and_then(|v| v.as_bool())
+                                .unwrap_or(false),
+                            version_prefix: spec
+                                .get("version_prefix")
+                                .and_then(|v| v.as_str())
+                                .map(|s| s.to_string()),
+                            version_regex: spec
+                                .get("version_regex")
+                                .and_then(|v| v.as_str())
+                                .map(|s| s.to_string()),
+                        };
+
+                        let version_info = if repo_url.is_some() {
+                            Self::check_package_version_with_options(
+                                &package_name,
+                                current_version,
+                                repo_url,
+                                forge_type,
+                                &compare_options,
+                            )
+                            .await
+                        } else if alpine_package.is_some() {
+                            Self::check_package_version_from_distro_repo(
+                                &package_name,
+                                current_version,
+                                alpine_package,
+                                "edge",
+                            )
+                            .await
+                        } else {
+                            continue;
+                        };
+
+                        if let Ok(version_info) = version_info {
                             versions.push(version_info);
                         }
                     }
@@ -198,3 +768,58 @@ impl VersionChecker {
         Ok(spec)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_status_update_available() {
+        let options = VersionCompareOptions::default();
+        let (status, reliable) = VersionChecker::resolve_status("1.2.0", Some("1.3.0"), &options);
+        assert_eq!(status, VersionStatus::UpdateAvailable);
+        assert!(reliable);
+    }
+
+    #[test]
+    fn test_resolve_status_up_to_date() {
+        let options = VersionCompareOptions::default();
+        let (status, reliable) = VersionChecker::resolve_status("2.0.0", Some("1.9.9"), &options);
+        assert_eq!(status, VersionStatus::UpToDate);
+        assert!(reliable);
+    }
+
+    #[test]
+    fn test_resolve_status_ignores_prerelease_when_requested() {
+        let options = VersionCompareOptions {
+            ignore_prereleases: true,
+            ..VersionCompareOptions::default()
+        };
+        let (status, reliable) =
+            VersionChecker::resolve_status("1.2.0", Some("1.3.0-rc.1"), &options);
+        assert_eq!(status, VersionStatus::UpToDate);
+        assert!(reliable);
+    }
+
+    #[test]
+    fn test_resolve_status_unparseable_falls_back_to_raw_comparison() {
+        let options = VersionCompareOptions::default();
+        let (status, reliable) =
+            VersionChecker::resolve_status("nightly-build", Some("nightly-build-2"), &options);
+        assert_eq!(status, VersionStatus::UpdateAvailable);
+        assert!(!reliable);
+
+        let (status, reliable) =
+            VersionChecker::resolve_status("nightly-build", Some("nightly-build"), &options);
+        assert_eq!(status, VersionStatus::UpToDate);
+        assert!(!reliable);
+    }
+
+    #[test]
+    fn test_resolve_status_no_upstream_is_unknown() {
+        let options = VersionCompareOptions::default();
+        let (status, reliable) = VersionChecker::resolve_status("1.0.0", None, &options);
+        assert_eq!(status, VersionStatus::Unknown);
+        assert!(reliable);
+    }
+}